@@ -0,0 +1,56 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Coin, StdError, StdResult, Uint128};
+use cw20::Cw20Coin;
+
+/// The balance an auction is currently holding: either a set of native
+/// coins or a single cw20 token. An auction only ever tracks one
+/// denom/token at a time, so `Native` is expected to hold at most one
+/// `Coin`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub enum Balance {
+    Native(Vec<Coin>),
+    Cw20(Cw20Coin),
+}
+
+impl Balance {
+    /// Build a `Native` balance from a message's attached coins. An auction
+    /// only ever tracks one denom, so reject more than one coin instead of
+    /// silently ignoring the rest. No coins at all gives an empty balance.
+    pub fn from_sent_funds(sent: Vec<Coin>) -> StdResult<Balance> {
+        if sent.len() > 1 {
+            return Err(StdError::generic_err(
+                "Sending more than one coin denom is not supported",
+            ));
+        }
+        Ok(Balance::Native(sent))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Balance::Native(coins) => coins.iter().all(|c| c.amount == Uint128(0)),
+            Balance::Cw20(coin) => coin.amount == Uint128(0),
+        }
+    }
+
+    /// The amount held, assuming (as an auction does) a single denom/token.
+    pub fn amount(&self) -> Uint128 {
+        match self {
+            Balance::Native(coins) => coins.get(0).map(|c| c.amount).unwrap_or(Uint128(0)),
+            Balance::Cw20(coin) => coin.amount,
+        }
+    }
+
+    /// True if `other` is denominated the same way as `self` - same native
+    /// denom, or the same cw20 contract address.
+    pub fn same_denom(&self, other: &Balance) -> bool {
+        match (self, other) {
+            (Balance::Native(a), Balance::Native(b)) => {
+                a.get(0).map(|c| &c.denom) == b.get(0).map(|c| &c.denom)
+            }
+            (Balance::Cw20(a), Balance::Cw20(b)) => a.address == b.address,
+            _ => false,
+        }
+    }
+}
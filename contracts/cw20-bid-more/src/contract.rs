@@ -1,17 +1,20 @@
 use cosmwasm_std::{
-    from_binary, log, to_binary, Api, BankMsg, Binary, CosmosMsg, Env, Extern, HandleResponse,
-    HumanAddr, InitResponse, Querier, StdError, StdResult, Storage, WasmMsg,
+    from_binary, log, to_binary, Api, BankMsg, Binary, BlockInfo, CosmosMsg, Env, Extern,
+    HandleResponse, HumanAddr, InitResponse, MigrateResponse, Querier, StdError, StdResult,
+    Storage, Uint128, WasmMsg,
 };
 use cw0::calc_range_start_string;
-use cw2::set_contract_version;
-use cw20::{Cw20Coin, Cw20CoinHuman, Cw20HandleMsg, Cw20ReceiveMsg};
+use cw2::{get_contract_version, set_contract_version};
+use cw20::{Cw20Coin, Cw20CoinHuman, Cw20HandleMsg, Cw20ReceiveMsg, Expiration};
+use cw721::{Cw721HandleMsg, Cw721ReceiveMsg};
+use semver::Version;
 
 use crate::balance::Balance;
 use crate::msg::{
-    is_valid_name, BalanceHuman, CreateMsg, DetailsResponse, HandleMsg, InitMsg, ListResponse,
-    QueryMsg, ReceiveMsg,
+    is_valid_name, BalanceHuman, CreateMsg, DetailsResponse, HandleMsg, InitMsg, ItemHuman,
+    ListResponse, MigrateMsg, QueryMsg, ReceiveMsg,
 };
-use crate::state::{all_auction_ids, auction, auction_read, Auction};
+use crate::state::{all_auction_ids, auction, auction_read, auction_v1_read, Auction, Item};
 
 // Version info, for migration info
 const CONTRACT_NAME: &str = "crates.io:cw20-bid-more";
@@ -27,6 +30,63 @@ pub fn init<S: Storage, A: Api, Q: Querier>(
     Ok(InitResponse::default())
 }
 
+/// Auctions written by contract versions before this one used the
+/// original `AuctionV1` layout and need their bucket entries rewritten.
+const FIRST_VERSION_WITH_RESERVE_AND_ITEMS: &str = "0.2.0";
+
+pub fn migrate<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    _env: Env,
+    _msg: MigrateMsg,
+) -> StdResult<MigrateResponse> {
+    let stored = get_contract_version(&deps.storage)?;
+    if stored.contract != CONTRACT_NAME {
+        return Err(StdError::generic_err(
+            "Cannot migrate from a different contract type",
+        ));
+    }
+
+    let stored_version = parse_version(&stored.version)?;
+    let current_version = parse_version(CONTRACT_VERSION)?;
+    if stored_version > current_version {
+        return Err(StdError::generic_err(
+            "Cannot migrate to an earlier contract version",
+        ));
+    }
+
+    if stored_version < parse_version(FIRST_VERSION_WITH_RESERVE_AND_ITEMS)? {
+        migrate_legacy_auctions(&mut deps.storage)?;
+    }
+
+    set_contract_version(&mut deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(MigrateResponse::default())
+}
+
+/// Parse a semver string, so version gating in `migrate` compares versions
+/// numerically instead of lexicographically (e.g. `0.9.0` < `0.10.0`).
+fn parse_version(raw: &str) -> StdResult<Version> {
+    Version::parse(raw).map_err(|e| StdError::generic_err(e.to_string()))
+}
+
+/// Rewrite every auction still stored in the pre-0.2.0 `AuctionV1` layout,
+/// defaulting the fields that version never had. The stored-version gate
+/// that calls this is only as good as `CARGO_PKG_VERSION` being bumped in
+/// lockstep with the schema change, so as a second line of defense, skip
+/// any id that already deserializes under the current `Auction` schema
+/// instead of trusting the gate alone - that would otherwise clobber an
+/// auction's `reserve_price`/`item`/etc. back to `None`.
+fn migrate_legacy_auctions<S: Storage>(storage: &mut S) -> StdResult<()> {
+    let ids = all_auction_ids(&*storage, None, usize::MAX)?;
+    for id in ids {
+        if auction_read(&*storage).load(id.as_bytes()).is_ok() {
+            continue;
+        }
+        let legacy = auction_v1_read(&*storage).load(id.as_bytes())?;
+        auction(storage).save(id.as_bytes(), &Auction::from(legacy))?;
+    }
+    Ok(())
+}
+
 pub fn handle<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
     env: Env,
@@ -34,6 +94,19 @@ pub fn handle<S: Storage, A: Api, Q: Querier>(
 ) -> StdResult<HandleResponse> {
     match msg {
         HandleMsg::Receive(msg) => try_receive(deps, env, msg),
+        HandleMsg::ReceiveNft(msg) => try_receive_nft(deps, env, msg),
+        HandleMsg::Create(create) => {
+            let balance = Balance::from_sent_funds(env.message.sent_funds.clone())?;
+            let sender = env.message.sender.clone();
+            try_create(deps, env, create, balance, None, sender)
+        }
+        HandleMsg::Bid { id } => {
+            let balance = Balance::from_sent_funds(env.message.sent_funds.clone())?;
+            let sender = env.message.sender.clone();
+            try_bid(deps, env, balance, id, sender)
+        }
+        HandleMsg::Settle { id } => try_settle(deps, env, id),
+        HandleMsg::Cancel { id } => try_cancel(deps, env, id),
     }
 }
 
@@ -51,16 +124,46 @@ pub fn try_receive<S: Storage, A: Api, Q: Querier>(
         amount: wrapper.amount,
     };
     match msg {
-        ReceiveMsg::Create(create) => try_create(deps, env, create, token, wrapper.sender),
-        ReceiveMsg::Bid(bid) => try_bid(deps, env, token, bid.id, wrapper.sender),
+        ReceiveMsg::Create(create) => {
+            try_create(deps, env, create, Balance::Cw20(token), None, wrapper.sender)
+        }
+        ReceiveMsg::Bid(bid) => try_bid(deps, env, Balance::Cw20(token), bid.id, wrapper.sender),
     }
 }
 
+/// A seller escrows an NFT to auction off by sending it to this contract
+/// via the cw721 `SendNft` flow; the embedded `msg` is the `CreateMsg` for
+/// the new auction.
+pub fn try_receive_nft<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    wrapper: Cw721ReceiveMsg,
+) -> StdResult<HandleResponse> {
+    let msg: CreateMsg = match wrapper.msg {
+        Some(bin) => from_binary(&bin),
+        None => Err(StdError::parse_err("CreateMsg", "no data")),
+    }?;
+    let bid_token = msg
+        .bid_token
+        .clone()
+        .ok_or_else(|| StdError::generic_err("bid_token is required to auction an item"))?;
+    let opening_balance = Balance::Cw20(Cw20Coin {
+        address: deps.api.canonical_address(&bid_token)?,
+        amount: Uint128(0),
+    });
+    let item = Item::Cw721 {
+        contract: deps.api.canonical_address(&env.message.sender)?,
+        token_id: wrapper.token_id,
+    };
+    try_create(deps, env, msg, opening_balance, Some(item), wrapper.sender)
+}
+
 pub fn try_create<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
     env: Env,
     msg: CreateMsg,
-    balance: Cw20Coin,
+    balance: Balance,
+    item: Option<Item>,
     sender: HumanAddr,
 ) -> StdResult<HandleResponse> {
     if !is_valid_name(&msg.id) {
@@ -83,6 +186,11 @@ pub fn try_create<S: Storage, A: Api, Q: Querier>(
         source: deps.api.canonical_address(&sender)?,
         expires: msg.expires,
         balance: balance,
+        item,
+        reserve_price: msg.reserve_price,
+        min_increment_bps: msg.min_increment_bps,
+        extension_window: msg.extension_window,
+        extension_amount: msg.extension_amount,
     };
 
     // Try to store it, fail if the id already exists
@@ -102,7 +210,7 @@ pub fn try_create<S: Storage, A: Api, Q: Querier>(
 pub fn try_bid<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
     env: Env,
-    token: Cw20Coin,
+    token: Balance,
     id: String,
     sender: HumanAddr,
 ) -> StdResult<HandleResponse> {
@@ -112,21 +220,55 @@ pub fn try_bid<S: Storage, A: Api, Q: Querier>(
     if my_auction.is_expired(&env.block) {
         return Err(StdError::generic_err("Auction has already expired"));
     }
-    // the bidder must use the same cw20 token as the current balance
-    if my_auction.balance.address != token.address {
-        return Err(StdError::generic_err("Must use the same token address"))
+    // the bidder must use the same denom/token as the current balance
+    if !my_auction.balance.same_denom(&token) {
+        return Err(StdError::generic_err(
+            "Must bid with the same denom or token as the current balance",
+        ));
     }
-    // new bid price must be higher than current bid price
-    if my_auction.balance.amount >= token.amount {
+    let current_amount = my_auction.balance.amount();
+    let no_qualifying_bid = my_auction.winner == my_auction.source;
+
+    // the first qualifying bid must clear the reserve price, if any
+    if no_qualifying_bid {
+        if let Some(reserve_price) = my_auction.reserve_price {
+            if token.amount() < reserve_price {
+                return Err(StdError::generic_err("Bid does not meet the reserve price"));
+            }
+        }
+    }
+
+    // each new bid must clear the current bid by at least min_increment_bps
+    let min_increment_bps = my_auction.min_increment_bps.unwrap_or(0) as u128;
+    let step = current_amount
+        .checked_mul(Uint128(min_increment_bps))
+        .map_err(|e| StdError::generic_err(e.to_string()))?
+        .checked_div(Uint128(10_000))
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+    let min_required = step
+        .checked_add(current_amount)
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+    if token.amount() <= current_amount || token.amount() < min_required {
         return Err(StdError::generic_err("Bid price not high enough"));
     }
 
     let current_winner = deps.api.human_address(&my_auction.winner)?;
     let messages = send_tokens(&deps.api, &env.contract.address, &current_winner, my_auction.balance.clone())?;
 
+    // push the deadline out if this bid landed inside the soft-close window,
+    // to give everyone a fair chance to counter-bid (anti-sniping)
+    let expires = match (my_auction.extension_window, my_auction.extension_amount) {
+        (Some(window), Some(amount)) => {
+            extend_expiration(my_auction.expires, &env.block, window, amount)
+        }
+        _ => my_auction.expires,
+    };
+    let extended = expires != my_auction.expires;
+
     let auction_to_save = Auction {
         winner: deps.api.canonical_address(&sender)?,
         balance: token,
+        expires,
         ..my_auction
     };
 
@@ -136,16 +278,109 @@ pub fn try_bid<S: Storage, A: Api, Q: Querier>(
         Some(_) => Ok(auction_to_save),
     })?;
 
-    // delete action from storage
-    // auction(&mut deps.storage).remove(id.as_bytes());
+    let mut log_entries = vec![log("action", "bid"), log("id", id), log("by", sender)];
+    if extended {
+        log_entries.push(log("new_expiration", expires));
+    }
 
     Ok(HandleResponse {
         messages: messages,
-        log: vec![log("action", "bid"), log("id", id), log("by", sender)],
+        log: log_entries,
+        data: None,
+    })
+}
+
+/// Settle an expired auction: the winning bid is released to `source`,
+/// and the auctioned item (if any) is delivered to `winner`. If no one
+/// ever out-bid the seller (`winner == source`), both the balance and the
+/// item simply refund to `source`.
+pub fn try_settle<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    id: String,
+) -> StdResult<HandleResponse> {
+    let my_auction = auction_read(&deps.storage).load(id.as_bytes())?;
+
+    if !my_auction.is_expired(&env.block) {
+        return Err(StdError::generic_err("Auction has not yet expired"));
+    }
+
+    let source = deps.api.human_address(&my_auction.source)?;
+    let winner = deps.api.human_address(&my_auction.winner)?;
+    let no_qualifying_bid = my_auction.winner == my_auction.source;
+
+    let mut messages = send_tokens(
+        &deps.api,
+        &env.contract.address,
+        &source,
+        my_auction.balance.clone(),
+    )?;
+    if let Some(item) = my_auction.item.clone() {
+        let recipient = if no_qualifying_bid { &source } else { &winner };
+        messages.push(send_item(&deps.api, recipient, item)?);
+    }
+
+    auction(&mut deps.storage).remove(id.as_bytes());
+
+    Ok(HandleResponse {
+        messages,
+        log: vec![log("action", "settle"), log("id", id)],
+        data: None,
+    })
+}
+
+/// The seller may cancel an auction that has not yet received a bid,
+/// refunding the balance and item back to themselves.
+pub fn try_cancel<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    id: String,
+) -> StdResult<HandleResponse> {
+    let my_auction = auction_read(&deps.storage).load(id.as_bytes())?;
+
+    let source = deps.api.human_address(&my_auction.source)?;
+    if env.message.sender != source {
+        return Err(StdError::unauthorized());
+    }
+    if my_auction.winner != my_auction.source {
+        return Err(StdError::generic_err(
+            "Cannot cancel an auction that already has a bid",
+        ));
+    }
+
+    let mut messages = send_tokens(
+        &deps.api,
+        &env.contract.address,
+        &source,
+        my_auction.balance.clone(),
+    )?;
+    if let Some(item) = my_auction.item.clone() {
+        messages.push(send_item(&deps.api, &source, item)?);
+    }
+
+    auction(&mut deps.storage).remove(id.as_bytes());
+
+    Ok(HandleResponse {
+        messages,
+        log: vec![log("action", "cancel"), log("id", id)],
         data: None,
     })
 }
 
+/// If `expires` falls within `window` (blocks or seconds, matching the
+/// `Expiration` variant) of `block`, push it forward by `amount`.
+fn extend_expiration(expires: Expiration, block: &BlockInfo, window: u64, amount: u64) -> Expiration {
+    match expires {
+        Expiration::AtHeight(h) if h.saturating_sub(block.height) < window => {
+            Expiration::AtHeight(h.saturating_add(amount))
+        }
+        Expiration::AtTime(t) if t.saturating_sub(block.time) < window => {
+            Expiration::AtTime(t.saturating_add(amount))
+        }
+        other => other,
+    }
+}
+
 fn parse_hex_32(data: &str) -> StdResult<Vec<u8>> {
     match hex::decode(data) {
         Ok(bin) => {
@@ -166,21 +401,61 @@ fn send_tokens<A: Api>(
     api: &A,
     from: &HumanAddr,
     to: &HumanAddr,
-    coin: Cw20Coin,
+    balance: Balance,
 ) -> StdResult<Vec<CosmosMsg>> {
-    if coin.is_empty() {
-        Ok(vec![])
-    } else {
-        let msg = Cw20HandleMsg::Transfer {
-            recipient: to.into(),
-            amount: coin.amount,
-        };
-        let exec = WasmMsg::Execute {
-            contract_addr: api.human_address(&coin.address)?,
-            msg: to_binary(&msg)?,
-            send: vec![],
-        };
-        Ok(vec![exec.into()])
+    if balance.is_empty() {
+        return Ok(vec![]);
+    }
+    match balance {
+        Balance::Native(coins) => Ok(vec![BankMsg::Send {
+            from_address: from.clone(),
+            to_address: to.clone(),
+            amount: coins,
+        }
+        .into()]),
+        Balance::Cw20(coin) => {
+            let msg = Cw20HandleMsg::Transfer {
+                recipient: to.into(),
+                amount: coin.amount,
+            };
+            let exec = WasmMsg::Execute {
+                contract_addr: api.human_address(&coin.address)?,
+                msg: to_binary(&msg)?,
+                send: vec![],
+            };
+            Ok(vec![exec.into()])
+        }
+    }
+}
+
+/// Deliver the auctioned item to `to`, whether it's a cw20 token or a
+/// cw721 NFT.
+fn send_item<A: Api>(api: &A, to: &HumanAddr, item: Item) -> StdResult<CosmosMsg> {
+    match item {
+        Item::Cw20(coin) => {
+            let msg = Cw20HandleMsg::Transfer {
+                recipient: to.into(),
+                amount: coin.amount,
+            };
+            Ok(WasmMsg::Execute {
+                contract_addr: api.human_address(&coin.address)?,
+                msg: to_binary(&msg)?,
+                send: vec![],
+            }
+            .into())
+        }
+        Item::Cw721 { contract, token_id } => {
+            let msg = Cw721HandleMsg::TransferNft {
+                recipient: to.into(),
+                token_id,
+            };
+            Ok(WasmMsg::Execute {
+                contract_addr: api.human_address(&contract)?,
+                msg: to_binary(&msg)?,
+                send: vec![],
+            }
+            .into())
+        }
     }
 }
 
@@ -201,17 +476,37 @@ fn query_details<S: Storage, A: Api, Q: Querier>(
     let my_auction = auction_read(&deps.storage).load(id.as_bytes())?;
 
     // Convert balance to human balance
-    let balance_human = Cw20CoinHuman {
-        address: deps.api.human_address(&my_auction.balance.address)?,
-        amount: my_auction.balance.amount,
+    let balance_human = match my_auction.balance.clone() {
+        Balance::Native(coins) => BalanceHuman::Native(coins),
+        Balance::Cw20(coin) => BalanceHuman::Cw20(Cw20CoinHuman {
+            address: deps.api.human_address(&coin.address)?,
+            amount: coin.amount,
+        }),
     };
 
+    let item_human = my_auction
+        .item
+        .map(|item| -> StdResult<ItemHuman> {
+            Ok(match item {
+                Item::Cw20(coin) => ItemHuman::Cw20(Cw20CoinHuman {
+                    address: deps.api.human_address(&coin.address)?,
+                    amount: coin.amount,
+                }),
+                Item::Cw721 { contract, token_id } => ItemHuman::Cw721 {
+                    contract: deps.api.human_address(&contract)?,
+                    token_id,
+                },
+            })
+        })
+        .transpose()?;
+
     let details = DetailsResponse {
         id,
         winner: deps.api.human_address(&my_auction.winner)?,
         source: deps.api.human_address(&my_auction.source)?,
         expires: my_auction.expires,
         balance: balance_human,
+        item: item_human,
     };
     Ok(details)
 }
@@ -237,7 +532,9 @@ mod tests {
     use super::*;
     use cosmwasm_std::testing::{mock_dependencies, mock_env, MOCK_CONTRACT_ADDR};
     use cosmwasm_std::{coins, from_binary, Coin, CosmosMsg, StdError, Uint128};
+    use cosmwasm_storage::bucket;
     use cw20::Expiration;
+    use crate::state::AuctionV1;
 
     const CANONICAL_LENGTH: usize = 20;
 
@@ -258,6 +555,507 @@ mod tests {
         assert_eq!(0, res.messages.len());
     }
 
+    fn dummy_auction<A: Api>(
+        api: &A,
+        source: &HumanAddr,
+        winner: &HumanAddr,
+        expires: Expiration,
+    ) -> Auction {
+        Auction {
+            winner: api.canonical_address(winner).unwrap(),
+            source: api.canonical_address(source).unwrap(),
+            expires,
+            balance: Balance::Cw20(Cw20Coin {
+                address: api
+                    .canonical_address(&HumanAddr::from("tokenaddr"))
+                    .unwrap(),
+                amount: Uint128(100),
+            }),
+            item: None,
+            reserve_price: None,
+            min_increment_bps: None,
+            extension_window: None,
+            extension_amount: None,
+        }
+    }
+
+    #[test]
+    fn test_settle_pays_winning_bid_to_source() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        let source = HumanAddr::from("source0001");
+        let winner = HumanAddr::from("winner0001");
+        let swap = dummy_auction(&deps.api, &source, &winner, Expiration::AtHeight(100));
+        auction(&mut deps.storage).save(b"swap0001", &swap).unwrap();
+
+        let env = mock_env_height("anyone", &[], 200);
+        let res = try_settle(&mut deps, env, "swap0001".to_string()).unwrap();
+        assert_eq!(1, res.messages.len());
+        assert_eq!(log("action", "settle"), res.log[0]);
+    }
+
+    #[test]
+    fn test_settle_before_expiry_fails() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        let source = HumanAddr::from("source0001");
+        let winner = HumanAddr::from("winner0001");
+        let swap = dummy_auction(&deps.api, &source, &winner, Expiration::AtHeight(100));
+        auction(&mut deps.storage).save(b"swap0001", &swap).unwrap();
+
+        let env = mock_env_height("anyone", &[], 1);
+        let res = try_settle(&mut deps, env, "swap0001".to_string());
+        match res {
+            Ok(_) => panic!("expected error"),
+            Err(StdError::GenericErr { msg, .. }) => {
+                assert_eq!(msg, "Auction has not yet expired".to_string())
+            }
+            Err(e) => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_cancel_refunds_source_with_no_bids() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        let source = HumanAddr::from("source0001");
+        let swap = dummy_auction(&deps.api, &source, &source, Expiration::AtHeight(100));
+        auction(&mut deps.storage).save(b"swap0001", &swap).unwrap();
+
+        let env = mock_env(&source, &[]);
+        let res = try_cancel(&mut deps, env, "swap0001".to_string()).unwrap();
+        assert_eq!(1, res.messages.len());
+        assert_eq!(log("action", "cancel"), res.log[0]);
+    }
+
+    #[test]
+    fn test_cancel_with_existing_bid_fails() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        let source = HumanAddr::from("source0001");
+        let winner = HumanAddr::from("winner0001");
+        let swap = dummy_auction(&deps.api, &source, &winner, Expiration::AtHeight(100));
+        auction(&mut deps.storage).save(b"swap0001", &swap).unwrap();
+
+        let env = mock_env(&source, &[]);
+        let res = try_cancel(&mut deps, env, "swap0001".to_string());
+        match res {
+            Ok(_) => panic!("expected error"),
+            Err(StdError::GenericErr { msg, .. }) => assert_eq!(
+                msg,
+                "Cannot cancel an auction that already has a bid".to_string()
+            ),
+            Err(e) => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_bid_with_native_coins_outbids_current_balance() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        let source = HumanAddr::from("source0001");
+        let winner = HumanAddr::from("winner0001");
+        let mut swap = dummy_auction(&deps.api, &source, &winner, Expiration::AtHeight(100));
+        swap.balance = Balance::Native(coins(100, "uatom"));
+        auction(&mut deps.storage).save(b"swap0001", &swap).unwrap();
+
+        let bidder = HumanAddr::from("bidder0001");
+        let env = mock_env_height(&bidder, &coins(150, "uatom"), 1);
+        let res = try_bid(
+            &mut deps,
+            env,
+            Balance::Native(coins(150, "uatom")),
+            "swap0001".to_string(),
+            bidder.clone(),
+        )
+        .unwrap();
+        assert_eq!(1, res.messages.len());
+        assert_eq!(log("action", "bid"), res.log[0]);
+
+        let stored = auction_read(&deps.storage).load(b"swap0001").unwrap();
+        assert_eq!(Balance::Native(coins(150, "uatom")), stored.balance);
+        assert_eq!(deps.api.canonical_address(&bidder).unwrap(), stored.winner);
+    }
+
+    #[test]
+    fn test_bid_with_mismatched_denom_fails() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        let source = HumanAddr::from("source0001");
+        let winner = HumanAddr::from("winner0001");
+        let mut swap = dummy_auction(&deps.api, &source, &winner, Expiration::AtHeight(100));
+        swap.balance = Balance::Native(coins(100, "uatom"));
+        auction(&mut deps.storage).save(b"swap0001", &swap).unwrap();
+
+        let bidder = HumanAddr::from("bidder0001");
+        let env = mock_env_height(&bidder, &coins(150, "uluna"), 1);
+        let res = try_bid(
+            &mut deps,
+            env,
+            Balance::Native(coins(150, "uluna")),
+            "swap0001".to_string(),
+            bidder,
+        );
+        match res {
+            Ok(_) => panic!("expected error"),
+            Err(StdError::GenericErr { msg, .. }) => assert_eq!(
+                msg,
+                "Must bid with the same denom or token as the current balance".to_string()
+            ),
+            Err(e) => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_bid_meets_reserve_and_min_increment() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        let source = HumanAddr::from("source0001");
+        let mut swap = dummy_auction(&deps.api, &source, &source, Expiration::AtHeight(100));
+        swap.balance = Balance::Native(coins(100, "uatom"));
+        swap.reserve_price = Some(Uint128(120));
+        swap.min_increment_bps = Some(1_000); // 10%
+        auction(&mut deps.storage).save(b"swap0001", &swap).unwrap();
+
+        // 120 clears both the reserve price (120) and the 10% minimum
+        // increment over the current balance of 100 (110).
+        let bidder = HumanAddr::from("bidder0001");
+        let env = mock_env_height(&bidder, &coins(120, "uatom"), 1);
+        let res = try_bid(
+            &mut deps,
+            env,
+            Balance::Native(coins(120, "uatom")),
+            "swap0001".to_string(),
+            bidder,
+        )
+        .unwrap();
+        assert_eq!(log("action", "bid"), res.log[0]);
+    }
+
+    #[test]
+    fn test_bid_below_reserve_price_fails() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        let source = HumanAddr::from("source0001");
+        let mut swap = dummy_auction(&deps.api, &source, &source, Expiration::AtHeight(100));
+        swap.balance = Balance::Native(coins(100, "uatom"));
+        swap.reserve_price = Some(Uint128(150));
+        auction(&mut deps.storage).save(b"swap0001", &swap).unwrap();
+
+        let bidder = HumanAddr::from("bidder0001");
+        let env = mock_env_height(&bidder, &coins(120, "uatom"), 1);
+        let res = try_bid(
+            &mut deps,
+            env,
+            Balance::Native(coins(120, "uatom")),
+            "swap0001".to_string(),
+            bidder,
+        );
+        match res {
+            Ok(_) => panic!("expected error"),
+            Err(StdError::GenericErr { msg, .. }) => {
+                assert_eq!(msg, "Bid does not meet the reserve price".to_string())
+            }
+            Err(e) => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_zero_amount_bid_on_fresh_auction_fails() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        let source = HumanAddr::from("source0001");
+        let mut swap = dummy_auction(&deps.api, &source, &source, Expiration::AtHeight(100));
+        swap.balance = Balance::Native(vec![]);
+        auction(&mut deps.storage).save(b"swap0001", &swap).unwrap();
+
+        let bidder = HumanAddr::from("bidder0001");
+        let env = mock_env_height(&bidder, &[], 1);
+        let res = try_bid(
+            &mut deps,
+            env,
+            Balance::Native(vec![]),
+            "swap0001".to_string(),
+            bidder,
+        );
+        match res {
+            Ok(_) => panic!("expected error"),
+            Err(StdError::GenericErr { msg, .. }) => {
+                assert_eq!(msg, "Bid price not high enough".to_string())
+            }
+            Err(e) => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_bid_below_min_increment_fails() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        let source = HumanAddr::from("source0001");
+        let winner = HumanAddr::from("winner0001");
+        let mut swap = dummy_auction(&deps.api, &source, &winner, Expiration::AtHeight(100));
+        swap.balance = Balance::Native(coins(100, "uatom"));
+        swap.min_increment_bps = Some(1_000); // 10%, so 110 is the minimum next bid
+
+        auction(&mut deps.storage).save(b"swap0001", &swap).unwrap();
+
+        let bidder = HumanAddr::from("bidder0001");
+        let env = mock_env_height(&bidder, &coins(105, "uatom"), 1);
+        let res = try_bid(
+            &mut deps,
+            env,
+            Balance::Native(coins(105, "uatom")),
+            "swap0001".to_string(),
+            bidder,
+        );
+        match res {
+            Ok(_) => panic!("expected error"),
+            Err(StdError::GenericErr { msg, .. }) => {
+                assert_eq!(msg, "Bid price not high enough".to_string())
+            }
+            Err(e) => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_bid_min_increment_overflow_is_rejected_not_panicked() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        let source = HumanAddr::from("source0001");
+        let winner = HumanAddr::from("winner0001");
+        let mut swap = dummy_auction(&deps.api, &source, &winner, Expiration::AtHeight(100));
+        swap.balance = Balance::Native(coins(u128::MAX, "uatom"));
+        swap.min_increment_bps = Some(u64::MAX);
+        auction(&mut deps.storage).save(b"swap0001", &swap).unwrap();
+
+        let bidder = HumanAddr::from("bidder0001");
+        let env = mock_env_height(&bidder, &coins(u128::MAX, "uatom"), 1);
+        let res = try_bid(
+            &mut deps,
+            env,
+            Balance::Native(coins(u128::MAX, "uatom")),
+            "swap0001".to_string(),
+            bidder,
+        );
+        match res {
+            Ok(_) => panic!("expected overflow to be rejected as an error"),
+            Err(StdError::GenericErr { .. }) => {}
+            Err(e) => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_bid_inside_soft_close_window_extends_expiration() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        let source = HumanAddr::from("source0001");
+        let winner = HumanAddr::from("winner0001");
+        let mut swap = dummy_auction(&deps.api, &source, &winner, Expiration::AtHeight(100));
+        swap.balance = Balance::Native(coins(100, "uatom"));
+        swap.extension_window = Some(10);
+        swap.extension_amount = Some(20);
+        auction(&mut deps.storage).save(b"swap0001", &swap).unwrap();
+
+        // height 95 is within 10 blocks of the 100-block deadline
+        let bidder = HumanAddr::from("bidder0001");
+        let env = mock_env_height(&bidder, &coins(150, "uatom"), 95);
+        let res = try_bid(
+            &mut deps,
+            env,
+            Balance::Native(coins(150, "uatom")),
+            "swap0001".to_string(),
+            bidder,
+        )
+        .unwrap();
+        assert_eq!(4, res.log.len());
+        assert_eq!(log("new_expiration", Expiration::AtHeight(120)), res.log[3]);
+
+        let stored = auction_read(&deps.storage).load(b"swap0001").unwrap();
+        assert_eq!(Expiration::AtHeight(120), stored.expires);
+    }
+
+    #[test]
+    fn test_bid_outside_soft_close_window_does_not_extend_expiration() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        let source = HumanAddr::from("source0001");
+        let winner = HumanAddr::from("winner0001");
+        let mut swap = dummy_auction(&deps.api, &source, &winner, Expiration::AtHeight(100));
+        swap.balance = Balance::Native(coins(100, "uatom"));
+        swap.extension_window = Some(10);
+        swap.extension_amount = Some(20);
+        auction(&mut deps.storage).save(b"swap0001", &swap).unwrap();
+
+        // height 50 is well outside the 10-block soft-close window
+        let bidder = HumanAddr::from("bidder0001");
+        let env = mock_env_height(&bidder, &coins(150, "uatom"), 50);
+        let res = try_bid(
+            &mut deps,
+            env,
+            Balance::Native(coins(150, "uatom")),
+            "swap0001".to_string(),
+            bidder,
+        )
+        .unwrap();
+        assert_eq!(3, res.log.len());
+
+        let stored = auction_read(&deps.storage).load(b"swap0001").unwrap();
+        assert_eq!(Expiration::AtHeight(100), stored.expires);
+    }
+
+    #[test]
+    fn test_extend_expiration_clamps_instead_of_overflowing() {
+        let env = mock_env_height("anyone", &[], 95);
+        let expires = extend_expiration(Expiration::AtHeight(100), &env.block, 10, u64::MAX);
+        assert_eq!(Expiration::AtHeight(u64::MAX), expires);
+
+        let mut env = mock_env_height("anyone", &[], 95);
+        env.block.time = u64::MAX - 5;
+        let expires = extend_expiration(Expiration::AtTime(u64::MAX), &env.block, 10, u64::MAX);
+        assert_eq!(Expiration::AtTime(u64::MAX), expires);
+    }
+
+    #[test]
+    fn test_receive_nft_creates_auction_with_item_escrowed() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        let nft_contract = HumanAddr::from("nft0001");
+        let seller = HumanAddr::from("seller0001");
+        let bid_token = HumanAddr::from("tokenaddr");
+
+        let create = CreateMsg {
+            id: "swap0001".to_string(),
+            expires: Expiration::AtHeight(100),
+            reserve_price: None,
+            min_increment_bps: None,
+            extension_window: None,
+            extension_amount: None,
+            bid_token: Some(bid_token.clone()),
+        };
+        let wrapper = Cw721ReceiveMsg {
+            sender: seller.clone(),
+            token_id: "token0001".to_string(),
+            msg: Some(to_binary(&create).unwrap()),
+        };
+
+        let env = mock_env_height(&nft_contract, &[], 1);
+        let res = try_receive_nft(&mut deps, env, wrapper).unwrap();
+        assert_eq!(log("action", "create"), res.log[0]);
+
+        let stored = auction_read(&deps.storage).load(b"swap0001").unwrap();
+        assert_eq!(
+            Some(Item::Cw721 {
+                contract: deps.api.canonical_address(&nft_contract).unwrap(),
+                token_id: "token0001".to_string(),
+            }),
+            stored.item
+        );
+    }
+
+    #[test]
+    fn test_receive_nft_without_bid_token_fails() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        let nft_contract = HumanAddr::from("nft0001");
+        let seller = HumanAddr::from("seller0001");
+
+        let create = CreateMsg {
+            id: "swap0001".to_string(),
+            expires: Expiration::AtHeight(100),
+            reserve_price: None,
+            min_increment_bps: None,
+            extension_window: None,
+            extension_amount: None,
+            bid_token: None,
+        };
+        let wrapper = Cw721ReceiveMsg {
+            sender: seller,
+            token_id: "token0001".to_string(),
+            msg: Some(to_binary(&create).unwrap()),
+        };
+
+        let env = mock_env(&nft_contract, &[]);
+        let res = try_receive_nft(&mut deps, env, wrapper);
+        match res {
+            Ok(_) => panic!("expected error"),
+            Err(StdError::GenericErr { msg, .. }) => {
+                assert_eq!(msg, "bid_token is required to auction an item".to_string())
+            }
+            Err(e) => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_settle_delivers_nft_item_to_winner() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        let source = HumanAddr::from("source0001");
+        let winner = HumanAddr::from("winner0001");
+        let mut swap = dummy_auction(&deps.api, &source, &winner, Expiration::AtHeight(100));
+        swap.item = Some(Item::Cw721 {
+            contract: deps.api.canonical_address(&HumanAddr::from("nft0001")).unwrap(),
+            token_id: "token0001".to_string(),
+        });
+        auction(&mut deps.storage).save(b"swap0001", &swap).unwrap();
+
+        let env = mock_env_height("anyone", &[], 200);
+        let res = try_settle(&mut deps, env, "swap0001".to_string()).unwrap();
+        // one message for the winning bid, one for the escrowed NFT
+        assert_eq!(2, res.messages.len());
+    }
+
+    #[test]
+    fn test_migrate_rewrites_legacy_auctions() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        set_contract_version(&mut deps.storage, CONTRACT_NAME, "0.1.0").unwrap();
+
+        let legacy = AuctionV1 {
+            winner: deps.api.canonical_address(&HumanAddr::from("source0001")).unwrap(),
+            source: deps.api.canonical_address(&HumanAddr::from("source0001")).unwrap(),
+            expires: Expiration::AtHeight(100),
+            balance: Cw20Coin {
+                address: deps.api.canonical_address(&HumanAddr::from("tokenaddr")).unwrap(),
+                amount: Uint128(100),
+            },
+        };
+        bucket(crate::state::PREFIX_AUCTION, &mut deps.storage)
+            .save(b"swap0001", &legacy)
+            .unwrap();
+
+        let env = mock_env("anyone", &[]);
+        migrate(&mut deps, env, MigrateMsg {}).unwrap();
+
+        let migrated = auction_read(&deps.storage).load(b"swap0001").unwrap();
+        assert_eq!(Balance::Cw20(legacy.balance), migrated.balance);
+        assert_eq!(None, migrated.item);
+        assert_eq!(None, migrated.reserve_price);
+
+        let version = get_contract_version(&deps.storage).unwrap();
+        assert_eq!(CONTRACT_VERSION, version.version);
+    }
+
+    #[test]
+    fn test_migrate_is_noop_for_auctions_already_on_new_schema() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        // Stored version is stale (pre-0.2.0), as if the version bump never
+        // shipped, but the auction itself was already created under the new
+        // code and has new-schema fields set.
+        set_contract_version(&mut deps.storage, CONTRACT_NAME, "0.1.0").unwrap();
+
+        let source = HumanAddr::from("source0001");
+        let winner = HumanAddr::from("winner0001");
+        let mut swap = dummy_auction(&deps.api, &source, &winner, Expiration::AtHeight(100));
+        swap.reserve_price = Some(Uint128(150));
+        auction(&mut deps.storage).save(b"swap0001", &swap).unwrap();
+
+        let env = mock_env("anyone", &[]);
+        migrate(&mut deps, env, MigrateMsg {}).unwrap();
+
+        let migrated = auction_read(&deps.storage).load(b"swap0001").unwrap();
+        assert_eq!(Some(Uint128(150)), migrated.reserve_price);
+    }
+
+    #[test]
+    fn test_migrate_from_newer_version_fails() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        set_contract_version(&mut deps.storage, CONTRACT_NAME, "99.0.0").unwrap();
+
+        let env = mock_env("anyone", &[]);
+        let res = migrate(&mut deps, env, MigrateMsg {});
+        match res {
+            Ok(_) => panic!("expected error"),
+            Err(StdError::GenericErr { msg, .. }) => assert_eq!(
+                msg,
+                "Cannot migrate to an earlier contract version".to_string()
+            ),
+            Err(e) => panic!("unexpected error: {:?}", e),
+        }
+    }
+
     /*    #[test]
     fn test_create() {
         let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
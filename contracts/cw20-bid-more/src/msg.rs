@@ -1,18 +1,35 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Coin, HumanAddr};
-use cw20::{Cw20CoinHuman, Cw20ReceiveMsg, Expiration, Cw20Coin};
+use cosmwasm_std::{Coin, HumanAddr, Uint128};
+use cw20::{Cw20CoinHuman, Cw20ReceiveMsg, Expiration};
+use cw721::Cw721ReceiveMsg;
 use crate::balance::Balance;
 
 #[derive(Serialize, Deserialize, JsonSchema)]
 pub struct InitMsg {}
 
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct MigrateMsg {}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum HandleMsg {
     /// This accepts a properly-encoded ReceiveMsg from a cw20 contract
     Receive(Cw20ReceiveMsg),
+    /// This accepts a properly-encoded ReceiveNftMsg from a cw721 contract,
+    /// escrowing the NFT as the item being auctioned
+    ReceiveNft(Cw721ReceiveMsg),
+    /// Create an auction funded with the native coins sent alongside this message
+    Create(CreateMsg),
+    /// Bid on an auction using the native coins sent alongside this message
+    Bid { id: String },
+    /// Anyone may settle an expired auction: the winning bid goes to
+    /// `source` and the auctioned item (if any) goes to `winner`.
+    Settle { id: String },
+    /// The seller may cancel an auction that has not yet received a bid,
+    /// refunding the balance and item back to `source`.
+    Cancel { id: String },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -30,6 +47,24 @@ pub struct CreateMsg {
     /// You can set expiration at time or at block height the contract is valid at.
     /// After the contract is expired, it can be returned to the original funder.
     pub expires: Expiration,
+    /// If set, no bid is accepted until the auction has a qualifying bid at
+    /// or above this amount.
+    pub reserve_price: Option<Uint128>,
+    /// If set, each new bid must exceed the current bid by at least this
+    /// many basis points (1/100 of a percent, so 10_000 = 100%).
+    pub min_increment_bps: Option<u64>,
+    /// If a bid lands within this many blocks/seconds (matching the
+    /// `Expiration` variant used by `expires`) of the deadline, the
+    /// deadline is pushed out by `extension_amount` to prevent sniping.
+    pub extension_window: Option<u64>,
+    /// How far to push `expires` out by when a bid lands inside
+    /// `extension_window`, in the same unit as `expires`.
+    pub extension_amount: Option<u64>,
+    /// The cw20 contract bids must be denominated in. Required when this
+    /// auction is created by escrowing a cw721 NFT via `ReceiveNft`, since
+    /// there the opening balance starts at zero with no token attached to
+    /// infer the denom from.
+    pub bid_token: Option<HumanAddr>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -77,7 +112,9 @@ pub struct DetailsResponse {
     /// Once an auction is expired, it can be claimed by the highest bidder (via "claim").
     pub expires: Expiration,
     /// Balance in native tokens or cw20 token, with human address
-    pub balance: Cw20CoinHuman,
+    pub balance: BalanceHuman,
+    /// The item being auctioned off, if any
+    pub item: Option<ItemHuman>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -85,3 +122,9 @@ pub enum BalanceHuman {
     Native(Vec<Coin>),
     Cw20(Cw20CoinHuman),
 }
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub enum ItemHuman {
+    Cw20(Cw20CoinHuman),
+    Cw721 { contract: HumanAddr, token_id: String },
+}
@@ -4,17 +4,41 @@ use serde::{Deserialize, Serialize};
 use crate::balance::Balance;
 use cosmwasm_std::{
     Binary, BlockInfo, CanonicalAddr, Order, ReadonlyStorage, StdError, StdResult, Storage,
+    Uint128,
 };
 use cosmwasm_storage::{bucket, bucket_read, prefixed_read, Bucket, ReadonlyBucket};
 use cw20::{Expiration, Cw20Coin};
 
+/// The asset being auctioned off.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub enum Item {
+    Cw20(Cw20Coin),
+    Cw721 {
+        contract: CanonicalAddr,
+        token_id: String,
+    },
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct Auction {
     pub winner: CanonicalAddr,
     pub source: CanonicalAddr,
     pub expires: Expiration,
-    /// Balance in cw20 token
-    pub balance: Cw20Coin,
+    /// Current bid balance, in native coins or a cw20 token
+    pub balance: Balance,
+    /// The asset being auctioned off, if any
+    pub item: Option<Item>,
+    /// No bid is accepted until the auction has a qualifying bid at or
+    /// above this amount
+    pub reserve_price: Option<Uint128>,
+    /// Each new bid must exceed the current one by at least this many
+    /// basis points
+    pub min_increment_bps: Option<u64>,
+    /// A bid landing within this many blocks/seconds of `expires` pushes
+    /// the deadline out by `extension_amount` (anti-sniping soft close)
+    pub extension_window: Option<u64>,
+    /// How far to push `expires` out by, in the same unit as `expires`
+    pub extension_amount: Option<u64>,
 }
 
 impl Auction {
@@ -23,6 +47,33 @@ impl Auction {
     }
 }
 
+/// The original on-chain `Auction` layout, from before reserve prices,
+/// bid extensions and item escrow existed. Kept around so `migrate` can
+/// upgrade auctions written by older contract versions in place.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct AuctionV1 {
+    pub winner: CanonicalAddr,
+    pub source: CanonicalAddr,
+    pub expires: Expiration,
+    pub balance: Cw20Coin,
+}
+
+impl From<AuctionV1> for Auction {
+    fn from(legacy: AuctionV1) -> Self {
+        Auction {
+            winner: legacy.winner,
+            source: legacy.source,
+            expires: legacy.expires,
+            balance: Balance::Cw20(legacy.balance),
+            item: None,
+            reserve_price: None,
+            min_increment_bps: None,
+            extension_window: None,
+            extension_amount: None,
+        }
+    }
+}
+
 pub const PREFIX_AUCTION: &[u8] = b"auction";
 
 /// Returns a bucket with all swaps (query by id)
@@ -36,6 +87,12 @@ pub fn auction_read<S: ReadonlyStorage>(storage: &S) -> ReadonlyBucket<S, Auctio
     bucket_read(PREFIX_AUCTION, storage)
 }
 
+/// Reads the auction bucket using the pre-upgrade `AuctionV1` layout, for
+/// use by `migrate` only.
+pub fn auction_v1_read<S: ReadonlyStorage>(storage: &S) -> ReadonlyBucket<S, AuctionV1> {
+    bucket_read(PREFIX_AUCTION, storage)
+}
+
 /// This returns the list of ids for all active swaps
 pub fn all_auction_ids<S: ReadonlyStorage>(
     storage: &S,
@@ -54,7 +111,6 @@ mod tests {
     use super::*;
 
     use cosmwasm_std::testing::MockStorage;
-    use cosmwasm_std::{Binary, Uint128};
 
     #[test]
     fn test_no_swap_ids() {
@@ -68,10 +124,15 @@ mod tests {
             winner: CanonicalAddr(Binary(b"recip".to_vec())),
             source: CanonicalAddr(Binary(b"source".to_vec())),
             expires: Expiration::default(),
-            balance: Cw20Coin{
-                address:CanonicalAddr(Binary(b"address".to_vec())),
+            balance: Balance::Cw20(Cw20Coin {
+                address: CanonicalAddr(Binary(b"address".to_vec())),
                 amount: Uint128(0),
-            }
+            }),
+            item: None,
+            reserve_price: None,
+            min_increment_bps: None,
+            extension_window: None,
+            extension_amount: None,
         }
     }
 